@@ -1,21 +1,20 @@
 use blinds::traits::*;
 use blinds::*;
 use golem::{Context, GolemError};
-use golem::attribute::{Attribute, ShaderType};
-use golem::input::{Color, Uniforms, Vec2, Vec4, VertexBuilder, vec2, rgba};
+use golem::buffer::BufferUsage;
 use golem::program::{Attribute, ShaderDescription};
 
 async fn app(window: Window, ctx: glow::Context, mut events: EventStream) -> Result<(), GolemError> {
     let mut ctx = Context::from_glow(ctx);
 
-    let vertices = [
+    let vertices: [f32; 18] = [
         // Position         Color
         -0.5, -0.5,         1.0, 0.0, 0.0, 1.0,
         0.5, -0.5,          0.0, 1.0, 0.0, 1.0,
         0.0, 0.5,           0.0, 0.0, 1.0, 1.0
     ];
 
-    let shader = ctx.new_shader(ShaderDescription {
+    let mut shader = ctx.new_shader(ShaderDescription {
         vertex_input: &[
             Attribute::Vector(2, "vert_position"),
             Attribute::Vector(4, "vert_color"),
@@ -32,16 +31,17 @@ async fn app(window: Window, ctx: glow::Context, mut events: EventStream) -> Res
         }"#
     })?;
 
-    let mut vb = ctx.new_vertex_buffer();
-    let mut eb = ctx.new_element_buffer();
-    vb.send_data(0, &vertices);
-    eb.send_data(0, &[0, 1, 2]);
+    // The triangle is uploaded once and drawn every frame, so hint StaticDraw
+    let mut vb = ctx.new_vertex_buffer(BufferUsage::StaticDraw)?;
+    let mut eb = ctx.new_element_buffer(BufferUsage::StaticDraw)?;
+    vb.set_data(0, &vertices);
+    eb.set_data(0, &[0u32, 1, 2]);
+
+    shader.bind(&vb);
 
-    let uniforms = Uniforms::new();
- 
     while let Some(_) = events.next().await {
-        ctx.clear(rgba(0.0, 0.0, 0.0, 0.0));
-        ctx.draw(&shader, &vb, &eb, &uniforms, &[0..3]);
+        ctx.clear(0.0, 0.0, 0.0, 0.0);
+        ctx.draw(&eb, 0..3)?;
         window.present();
     }
 
@@ -0,0 +1,67 @@
+use crate::Context;
+
+/// A hint to the driver about how the contents of a buffer will be used
+///
+/// These map directly onto the GL usage enums; picking the right one lets the
+/// driver place the data in the most appropriate memory.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum BufferUsage {
+    /// Written once and drawn a handful of times
+    StreamDraw,
+    /// Rewritten and drawn repeatedly
+    DynamicDraw,
+    /// Written once and drawn many times
+    StaticDraw,
+}
+
+impl BufferUsage {
+    pub(crate) fn gl_usage(self) -> u32 {
+        match self {
+            BufferUsage::StreamDraw => glow::STREAM_DRAW,
+            BufferUsage::DynamicDraw => glow::DYNAMIC_DRAW,
+            BufferUsage::StaticDraw => glow::STATIC_DRAW,
+        }
+    }
+}
+
+/// The shared backing for the typed buffer wrappers
+pub struct Buffer {
+    pub(crate) ctx: Context,
+    pub(crate) id: u32,
+    pub(crate) length: usize,
+    pub(crate) usage: BufferUsage,
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        self.ctx.delete_buffer(self.id);
+    }
+}
+
+/// A buffer of vertex data, uploaded to the GPU
+pub struct VertexBuffer(pub(crate) Buffer);
+
+impl VertexBuffer {
+    /// Upload `data` into the buffer, starting at the given element offset
+    ///
+    /// The buffer grows as needed, using the [`BufferUsage`] it was created with.
+    pub fn set_data<T: bytemuck::Pod>(&mut self, start: usize, data: &[T]) {
+        self.0.ctx.bind(&self.0, glow::ARRAY_BUFFER);
+        let Buffer { ctx, length, usage, .. } = &mut self.0;
+        ctx.send_data(glow::ARRAY_BUFFER, length, *usage, start, data);
+    }
+}
+
+/// A buffer of vertex indices, uploaded to the GPU
+pub struct ElementBuffer(pub(crate) Buffer);
+
+impl ElementBuffer {
+    /// Upload `data` into the buffer, starting at the given element offset
+    ///
+    /// The buffer grows as needed, using the [`BufferUsage`] it was created with.
+    pub fn set_data<T: bytemuck::Pod>(&mut self, start: usize, data: &[T]) {
+        self.0.ctx.bind(&self.0, glow::ELEMENT_ARRAY_BUFFER);
+        let Buffer { ctx, length, usage, .. } = &mut self.0;
+        ctx.send_data(glow::ELEMENT_ARRAY_BUFFER, length, *usage, start, data);
+    }
+}
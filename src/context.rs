@@ -1,8 +1,10 @@
 use glow::HasContext;
 use crate::GolemError;
-use crate::buffer::{Buffer, ElementBuffer, VertexBuffer};
-use crate::objects::{ColorFormat, GeometryType, Surface, Texture, UniformValue};
-use crate::program::{Attribute, Position, Uniform, ShaderDescription, ShaderProgram};
+use crate::buffer::{Buffer, BufferUsage, ElementBuffer, VertexBuffer};
+use crate::objects::{BlendMode, ColorFormat, DepthTestFunction, Face, GeometryType, Surface, Texture, UniformValue};
+use crate::program::{Attribute, Position, Uniform, ShaderDescription, ShaderProgram, UniformLocation};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::ops::Range;
 use std::rc::Rc;
 
@@ -11,6 +13,10 @@ pub struct Context(Rc<ContextContents>);
 struct ContextContents {
     gl: glow::Context,
     vao: u32,
+    window_viewport: Cell<[i32; 4]>,
+    blend_mode: Cell<Option<BlendMode>>,
+    depth_test: Cell<Option<DepthTestFunction>>,
+    face_cull: Cell<Option<Face>>,
 }
 
 impl Drop for ContextContents {
@@ -54,15 +60,85 @@ impl Context {
         #[cfg(target_arch = "wasm32")]
         let vao = 0;
 
+        // Seed the saved viewport from the live GL state, so `reset_target` restores
+        // something sensible even if the user never calls `set_viewport`
+        let mut viewport = [0; 4];
+        unsafe {
+            gl.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport);
+        }
+
         let contents = Rc::new(ContextContents {
             gl,
             vao,
+            window_viewport: Cell::new(viewport),
+            blend_mode: Cell::new(None),
+            depth_test: Cell::new(None),
+            face_cull: Cell::new(None),
         });
 
 
         Context(contents)
     }
 
+    /// Install a GL debug-message callback that forwards to the `log` crate
+    ///
+    /// This requires a debug GL context, so it is opt-in rather than enabled in
+    /// [`from_glow`](Context::from_glow). Messages are logged at a level chosen from
+    /// their reported severity. Desktop GL only; a no-op on WebGL.
+    pub fn enable_debug(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        unsafe {
+            let gl = &self.0.gl;
+            gl.enable(glow::DEBUG_OUTPUT);
+            gl.debug_message_callback(|source, msg_type, id, severity, message| {
+                log_debug_message(source, msg_type, id, severity, message, None);
+            });
+        }
+    }
+
+    /// Like [`enable_debug`](Context::enable_debug), but also forwards each message to a sink
+    ///
+    /// The sink receives the formatted diagnostic string, for surfacing GL messages
+    /// in an application's own logging or on-screen console.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn enable_debug_with_sink(&self, sink: impl Fn(&str) + 'static) {
+        unsafe {
+            let gl = &self.0.gl;
+            gl.enable(glow::DEBUG_OUTPUT);
+            gl.debug_message_callback(move |source, msg_type, id, severity, message| {
+                log_debug_message(source, msg_type, id, severity, message, Some(&sink));
+            });
+        }
+    }
+
+    /// Drain the GL error queue, returning the first error as a [`GolemError`]
+    ///
+    /// Call after a suspect sequence of commands to turn silent GL misbehavior into
+    /// an actionable error. Returns `Ok(())` when the queue is empty.
+    pub fn check_error(&self) -> Result<(), GolemError> {
+        let gl = &self.0.gl;
+        let mut result = Ok(());
+        loop {
+            let error = unsafe { gl.get_error() };
+            if error == glow::NO_ERROR {
+                break;
+            }
+            let mapped = match error {
+                glow::INVALID_ENUM => GolemError::InvalidEnum,
+                glow::INVALID_VALUE => GolemError::InvalidValue,
+                glow::INVALID_OPERATION => GolemError::InvalidOperation,
+                glow::INVALID_FRAMEBUFFER_OPERATION => GolemError::InvalidFramebufferOperation,
+                glow::OUT_OF_MEMORY => GolemError::OutOfMemory,
+                other => GolemError::ContextError(format!("Unknown GL error: {:#x}", other)),
+            };
+            // Keep the first error, but keep draining so the queue is left empty
+            if result.is_ok() {
+                result = Err(mapped);
+            }
+        }
+        result
+    }
+
     pub fn new_shader(&self, desc: ShaderDescription) -> Result<ShaderProgram, GolemError> {
         let gl = &self.0.gl;
         unsafe {
@@ -126,27 +202,29 @@ impl Context {
                 vertex,
                 fragment,
                 input: desc.vertex_input.iter().cloned().collect(),
+                uniform_locations: RefCell::new(HashMap::new()),
             })
         }
     }
 
-    fn new_buffer(&self) -> Result<Buffer, GolemError> {
+    fn new_buffer(&self, usage: BufferUsage) -> Result<Buffer, GolemError> {
         let id = unsafe { self.0.gl.create_buffer() }?;
         let ctx = Context(self.0.clone());
 
         Ok(Buffer {
             ctx,
             id,
-            length: 0
+            length: 0,
+            usage,
         })
     }
 
-    pub fn new_vertex_buffer(&self) -> Result<VertexBuffer, GolemError> {
-        Ok(VertexBuffer(self.new_buffer()?))
+    pub fn new_vertex_buffer(&self, usage: BufferUsage) -> Result<VertexBuffer, GolemError> {
+        Ok(VertexBuffer(self.new_buffer(usage)?))
     }
 
-    pub fn new_element_buffer(&self) -> Result<ElementBuffer, GolemError> {
-        Ok(ElementBuffer(self.new_buffer()?))
+    pub fn new_element_buffer(&self, usage: BufferUsage) -> Result<ElementBuffer, GolemError> {
+        Ok(ElementBuffer(self.new_buffer(usage)?))
     }
 
     pub fn new_texture(&self, image: &[u8], width: u32, height: u32, color: ColorFormat) -> Result<Texture, GolemError> {
@@ -166,17 +244,44 @@ impl Context {
             gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
             gl.tex_image_2d(glow::TEXTURE_2D, 0, glow::RGBA as i32, width as i32,
                             height as i32, 0, format, glow::UNSIGNED_BYTE, Some(image));
-            // TODO: is this important
-            //gl.generate_mipmap(glow::TEXTURE_2D);
             gl.bind_texture(glow::TEXTURE_2D, None);
 
             Ok(Texture {
                 ctx: Context(self.0.clone()),
                 id,
+                width,
+                height,
             })
         }
     }
 
+    pub(crate) fn set_texture_filter(&self, id: u32, kind: u32, filter: crate::objects::TextureFilter) {
+        let gl = &self.0.gl;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(id));
+            gl.tex_parameter_i32(glow::TEXTURE_2D, kind, filter.gl_filter() as i32);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    pub(crate) fn set_texture_wrap(&self, id: u32, kind: u32, wrap: crate::objects::TextureWrap) {
+        let gl = &self.0.gl;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(id));
+            gl.tex_parameter_i32(glow::TEXTURE_2D, kind, wrap.gl_wrap() as i32);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    pub(crate) fn generate_texture_mipmap(&self, id: u32) {
+        let gl = &self.0.gl;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(id));
+            gl.generate_mipmap(glow::TEXTURE_2D);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
     pub(crate) fn bind_texture(&self, id: u32, texture_unit: u32) {
         let gl = &self.0.gl;
         unsafe {
@@ -191,7 +296,7 @@ impl Context {
         }
     }
 
-    pub(crate) fn send_data<T: bytemuck::Pod>(&self, bind: u32, length: &mut usize, start: usize, data: &[T]) {
+    pub(crate) fn send_data<T: bytemuck::Pod>(&self, bind: u32, length: &mut usize, usage: BufferUsage, start: usize, data: &[T]) {
         use std::mem::size_of;
         let data_start = size_of::<T>() * start;
         let u8_buffer = bytemuck::cast_slice(data);
@@ -201,19 +306,240 @@ impl Context {
             if data_length + start >= *length {
                 log::trace!("Resizing buffer to hold new data");
                 let new_length = (data_length + data_start) * 2;
-                gl.buffer_data_size(bind, new_length as i32, glow::STREAM_DRAW);
+                gl.buffer_data_size(bind, new_length as i32, usage.gl_usage());
                 *length = new_length;
             }
             gl.buffer_sub_data_u8_slice(bind, start as i32, u8_buffer);
         };
     }
 
-    pub fn set_target(&mut self, _surface: &Surface) {
-        unimplemented!();
+    /// Read back a rectangle of pixels from the currently bound framebuffer
+    ///
+    /// Useful for screenshots or reading results computed into a [`Surface`]. The
+    /// returned buffer is tightly packed as `width * height` pixels in the given
+    /// [`ColorFormat`], one byte per channel.
+    pub fn read_pixels(&self, x: u32, y: u32, width: u32, height: u32, color: ColorFormat) -> Vec<u8> {
+        let channels = match color {
+            ColorFormat::RGB => 3,
+            ColorFormat::RGBA => 4,
+        };
+        let mut buffer = vec![0; (width * height * channels) as usize];
+        let gl = &self.0.gl;
+        unsafe {
+            // GL defaults to a 4-byte pack alignment, which would pad each row and
+            // overrun a tightly-packed RGB buffer; force tightly-packed rows instead
+            gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+            gl.read_pixels(
+                x as i32,
+                y as i32,
+                width as i32,
+                height as i32,
+                color.gl_format(),
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(&mut buffer),
+            );
+        }
+        buffer
+    }
+
+    pub(crate) fn new_surface(&self, texture: Texture, depth: bool) -> Result<Surface, GolemError> {
+        let gl = &self.0.gl;
+        unsafe {
+            let fbo = gl.create_framebuffer()?;
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+            gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(texture.id), 0);
+
+            // A depth-stencil renderbuffer lets the surface be used for depth-tested
+            // passes; color-only passes can skip it to save the memory
+            let depth_stencil = if depth {
+                let depth_stencil = gl.create_renderbuffer()?;
+                gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth_stencil));
+                gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH24_STENCIL8, texture.width as i32, texture.height as i32);
+                gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_STENCIL_ATTACHMENT, glow::RENDERBUFFER, Some(depth_stencil));
+                gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+                Some(depth_stencil)
+            } else {
+                None
+            };
+
+            let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                gl.delete_framebuffer(fbo);
+                if let Some(depth_stencil) = depth_stencil {
+                    gl.delete_renderbuffer(depth_stencil);
+                }
+                return Err(GolemError::FramebufferError(framebuffer_status_message(status)));
+            }
+
+            let (width, height) = (texture.width, texture.height);
+            Ok(Surface {
+                ctx: Context(self.0.clone()),
+                id: fbo,
+                width,
+                height,
+                texture: Some(texture),
+                color_renderbuffer: None,
+                depth_stencil,
+                samples: 1,
+            })
+        }
+    }
+
+    pub(crate) fn new_multisampled_surface(&self, width: u32, height: u32, samples: u32) -> Result<Surface, GolemError> {
+        let gl = &self.0.gl;
+        let max_samples = unsafe { gl.get_parameter_i32(glow::MAX_SAMPLES) };
+        if max_samples <= 0 {
+            return Err(GolemError::ContextError("Multisampling is not supported by this context".to_owned()));
+        }
+        let samples = samples.clamp(1, max_samples as u32);
+        unsafe {
+            let fbo = gl.create_framebuffer()?;
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(fbo));
+
+            let color = gl.create_renderbuffer()?;
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(color));
+            gl.renderbuffer_storage_multisample(glow::RENDERBUFFER, samples as i32, glow::RGBA8, width as i32, height as i32);
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(color));
+
+            let depth_stencil = gl.create_renderbuffer()?;
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth_stencil));
+            gl.renderbuffer_storage_multisample(glow::RENDERBUFFER, samples as i32, glow::DEPTH24_STENCIL8, width as i32, height as i32);
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_STENCIL_ATTACHMENT, glow::RENDERBUFFER, Some(depth_stencil));
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+            let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                gl.delete_framebuffer(fbo);
+                gl.delete_renderbuffer(color);
+                gl.delete_renderbuffer(depth_stencil);
+                return Err(GolemError::FramebufferError(framebuffer_status_message(status)));
+            }
+
+            Ok(Surface {
+                ctx: Context(self.0.clone()),
+                id: fbo,
+                width,
+                height,
+                texture: None,
+                color_renderbuffer: Some(color),
+                depth_stencil: Some(depth_stencil),
+                samples,
+            })
+        }
+    }
+
+    pub(crate) fn resolve_surface(&self, source: u32, texture: &Texture, width: u32, height: u32) -> Result<(), GolemError> {
+        let gl = &self.0.gl;
+        unsafe {
+            let destination = gl.create_framebuffer()?;
+            gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(destination));
+            gl.framebuffer_texture_2d(glow::DRAW_FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(texture.id), 0);
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(source));
+            gl.blit_framebuffer(
+                0, 0, width as i32, height as i32,
+                0, 0, width as i32, height as i32,
+                glow::COLOR_BUFFER_BIT, glow::LINEAR,
+            );
+            gl.bind_framebuffer(glow::READ_FRAMEBUFFER, None);
+            gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, None);
+            gl.delete_framebuffer(destination);
+        }
+        Ok(())
+    }
+
+    /// Record the window's viewport, so [`reset_target`](Context::reset_target) can restore it
+    ///
+    /// Call this whenever the window is created or resized; it sets the GL viewport and
+    /// remembers the dimensions to fall back to after rendering to a [`Surface`].
+    pub fn set_viewport(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.0.window_viewport.set([x, y, width, height]);
+        unsafe {
+            self.0.gl.viewport(x, y, width, height);
+        }
+    }
+
+    pub fn set_target(&mut self, surface: &Surface) {
+        let gl = &self.0.gl;
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(surface.id));
+            gl.viewport(0, 0, surface.width as i32, surface.height as i32);
+        }
     }
 
     pub fn reset_target(&mut self) {
-        unimplemented!();
+        let [x, y, width, height] = self.0.window_viewport.get();
+        let gl = &self.0.gl;
+        unsafe {
+            gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+            gl.viewport(x, y, width, height);
+        }
+    }
+
+    /// Configure alpha blending, or disable it by passing `None`
+    ///
+    /// Redundant changes are skipped, so it is cheap to call every frame.
+    pub fn set_blend_mode(&mut self, blend: Option<BlendMode>) {
+        if self.0.blend_mode.get() == blend {
+            return;
+        }
+        let gl = &self.0.gl;
+        unsafe {
+            match blend {
+                Some(mode) => {
+                    gl.enable(glow::BLEND);
+                    gl.blend_func_separate(
+                        mode.source_color.gl_factor(),
+                        mode.destination_color.gl_factor(),
+                        mode.source_alpha.gl_factor(),
+                        mode.destination_alpha.gl_factor(),
+                    );
+                }
+                None => gl.disable(glow::BLEND),
+            }
+        }
+        self.0.blend_mode.set(blend);
+    }
+
+    /// Configure the depth test comparison, or disable depth testing with `None`
+    ///
+    /// Redundant changes are skipped, so it is cheap to call every frame.
+    pub fn set_depth_test(&mut self, depth: Option<DepthTestFunction>) {
+        if self.0.depth_test.get() == depth {
+            return;
+        }
+        let gl = &self.0.gl;
+        unsafe {
+            match depth {
+                Some(function) => {
+                    gl.enable(glow::DEPTH_TEST);
+                    gl.depth_func(function.gl_function());
+                }
+                None => gl.disable(glow::DEPTH_TEST),
+            }
+        }
+        self.0.depth_test.set(depth);
+    }
+
+    /// Configure which triangle facings are culled, or disable culling with `None`
+    ///
+    /// Redundant changes are skipped, so it is cheap to call every frame.
+    pub fn set_face_cull(&mut self, face: Option<Face>) {
+        if self.0.face_cull.get() == face {
+            return;
+        }
+        let gl = &self.0.gl;
+        unsafe {
+            match face {
+                Some(face) => {
+                    gl.enable(glow::CULL_FACE);
+                    gl.cull_face(face.gl_face());
+                }
+                None => gl.disable(glow::CULL_FACE),
+            }
+        }
+        self.0.face_cull.set(face);
     }
 
     pub fn clear(&mut self, r: f32, g: f32, b: f32, a: f32) {
@@ -284,9 +610,18 @@ impl Context {
     }
 
 
-    pub(crate) fn bind_uniform(&self, id: u32, name: &str, uniform: UniformValue) -> Result<(), GolemError> {
+    pub(crate) fn get_uniform_location(&self, id: u32, name: &str) -> Option<UniformLocation> {
+        unsafe { self.0.gl.get_uniform_location(id, name) }.map(UniformLocation::new)
+    }
+
+    pub(crate) fn bind_uniform(&self, location: Option<&UniformLocation>, uniform: UniformValue) {
+        // A `None` location means GL has no such active uniform; skip the FFI call entirely
+        let location = match location {
+            Some(location) => location.raw(),
+            None => return,
+        };
+        let location = Some(location);
         let gl = &self.0.gl;
-        let location = unsafe { gl.get_uniform_location(id, name) };
         use UniformValue::*;
         unsafe {
             match uniform {
@@ -303,8 +638,6 @@ impl Context {
                 Matrix4(mat) => gl.uniform_matrix_4_f32_slice(location, false, &mat),
             }
         }
-
-        Ok(())
     }
 
     pub(crate) fn delete_shader(&self, id: u32, fragment: u32, vertex: u32) {
@@ -328,7 +661,54 @@ impl Context {
         }
     }
 
-    pub(crate) fn delete_surface(&self, _id: u32) {
-        unimplemented!();
+    pub(crate) fn delete_surface(&self, id: u32, renderbuffers: &[u32]) {
+        let gl = &self.0.gl;
+        unsafe {
+            for &renderbuffer in renderbuffers {
+                gl.delete_renderbuffer(renderbuffer);
+            }
+            gl.delete_framebuffer(id);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn log_debug_message(source: u32, msg_type: u32, id: u32, severity: u32, message: &str, sink: Option<&dyn Fn(&str)>) {
+    let level = match severity {
+        glow::DEBUG_SEVERITY_HIGH => log::Level::Error,
+        glow::DEBUG_SEVERITY_MEDIUM => log::Level::Warn,
+        glow::DEBUG_SEVERITY_LOW => log::Level::Info,
+        _ => log::Level::Debug,
+    };
+    let source = match source {
+        glow::DEBUG_SOURCE_API => "api",
+        glow::DEBUG_SOURCE_SHADER_COMPILER => "shader-compiler",
+        glow::DEBUG_SOURCE_WINDOW_SYSTEM => "window-system",
+        glow::DEBUG_SOURCE_THIRD_PARTY => "third-party",
+        glow::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    };
+    let kind = match msg_type {
+        glow::DEBUG_TYPE_ERROR => "error",
+        glow::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated",
+        glow::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined-behavior",
+        glow::DEBUG_TYPE_PORTABILITY => "portability",
+        glow::DEBUG_TYPE_PERFORMANCE => "performance",
+        _ => "other",
+    };
+    let formatted = format!("GL debug [{}/{}, id {}]: {}", source, kind, id, message);
+    log::log!(level, "{}", formatted);
+    if let Some(sink) = sink {
+        sink(&formatted);
     }
 }
+
+fn framebuffer_status_message(status: u32) -> String {
+    let reason = match status {
+        glow::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => "incomplete attachment",
+        glow::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => "missing attachment",
+        glow::FRAMEBUFFER_UNSUPPORTED => "combination of formats is unsupported",
+        _ => "unknown cause",
+    };
+    format!("Framebuffer incomplete: {} (status {:#x})", reason, status)
+}
@@ -1,5 +1,4 @@
 // TODO: validate vec and matrix dimensions
-// TODO: add out-of-memory to GolemError?
 
 pub mod buffer;
 pub mod objects;
@@ -16,6 +15,22 @@ pub enum GolemError {
     ShaderCompilationError(String),
     /// Some general error bubbling up from the GL context
     ContextError(String),
+    /// A [`Surface`](objects::Surface) was created with an incomplete framebuffer
+    ///
+    /// The string is the human-readable status reported by `glCheckFramebufferStatus`
+    FramebufferError(String),
+    /// A draw was dispatched without a shader program bound
+    NoBoundProgram,
+    /// A GL enum argument was not legal for the call (`GL_INVALID_ENUM`)
+    InvalidEnum,
+    /// A GL numeric argument was out of range (`GL_INVALID_VALUE`)
+    InvalidValue,
+    /// A GL call was not allowed in the current state (`GL_INVALID_OPERATION`)
+    InvalidOperation,
+    /// A framebuffer was read from or written to while incomplete (`GL_INVALID_FRAMEBUFFER_OPERATION`)
+    InvalidFramebufferOperation,
+    /// The GL driver could not allocate enough memory for the command (`GL_OUT_OF_MEMORY`)
+    OutOfMemory,
 }
 
 impl From<String> for GolemError {
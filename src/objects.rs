@@ -0,0 +1,363 @@
+use crate::{Context, GolemError};
+use glow::HasContext;
+
+/// The layout of the color channels in an image or texture
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum ColorFormat {
+    /// A red, green, and blue channel, with no transparency
+    RGB,
+    /// A red, green, blue, and alpha channel
+    RGBA,
+}
+
+impl ColorFormat {
+    pub(crate) fn gl_format(self) -> u32 {
+        match self {
+            ColorFormat::RGB => glow::RGB,
+            ColorFormat::RGBA => glow::RGBA,
+        }
+    }
+}
+
+/// The primitive to assemble the vertices into when drawing
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum GeometryType {
+    Points,
+    Lines,
+    LineStrip,
+    LineLoop,
+    TriangleStrip,
+    TriangleFan,
+    Triangles,
+}
+
+/// How a texture is sampled when scaled up or down
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum TextureFilter {
+    /// Take the nearest texel, giving crisp pixel-art edges
+    Nearest,
+    /// Interpolate between the four nearest texels
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear,
+}
+
+impl TextureFilter {
+    pub(crate) fn gl_filter(self) -> u32 {
+        use TextureFilter::*;
+        match self {
+            Nearest => glow::NEAREST,
+            Linear => glow::LINEAR,
+            NearestMipmapNearest => glow::NEAREST_MIPMAP_NEAREST,
+            LinearMipmapNearest => glow::LINEAR_MIPMAP_NEAREST,
+            NearestMipmapLinear => glow::NEAREST_MIPMAP_LINEAR,
+            LinearMipmapLinear => glow::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+}
+
+/// How texture coordinates outside the `[0, 1]` range are resolved
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum TextureWrap {
+    /// Clamp the coordinate to the edge texel
+    Clamp,
+    /// Tile the texture, wrapping around
+    Repeat,
+    /// Tile the texture, mirroring on each repeat
+    MirroredRepeat,
+}
+
+impl TextureWrap {
+    pub(crate) fn gl_wrap(self) -> u32 {
+        match self {
+            TextureWrap::Clamp => glow::CLAMP_TO_EDGE,
+            TextureWrap::Repeat => glow::REPEAT,
+            TextureWrap::MirroredRepeat => glow::MIRRORED_REPEAT,
+        }
+    }
+}
+
+/// A 2D texture, stored on the GPU
+///
+/// Once wrapped in a [`Surface`], a texture is the target of offscreen rendering.
+/// Neither this type nor [`UniformValue`] binds a texture to a shader sampler.
+pub struct Texture {
+    pub(crate) ctx: Context,
+    pub(crate) id: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl Texture {
+    /// The raw OpenGL handle backing this texture
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The width in pixels the texture was created with
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height in pixels the texture was created with
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Set the filter used when the texture is minified (drawn smaller than its size)
+    ///
+    /// Pass one of the `*Mipmap*` variants only after calling [`generate_mipmaps`].
+    ///
+    /// [`generate_mipmaps`]: Texture::generate_mipmaps
+    pub fn set_minification(&self, filter: TextureFilter) {
+        self.ctx.set_texture_filter(self.id, glow::TEXTURE_MIN_FILTER, filter);
+    }
+
+    /// Set the filter used when the texture is magnified (drawn larger than its size)
+    pub fn set_magnification(&self, filter: TextureFilter) {
+        self.ctx.set_texture_filter(self.id, glow::TEXTURE_MAG_FILTER, filter);
+    }
+
+    /// Set the horizontal (S) wrapping mode
+    pub fn set_wrap_h(&self, wrap: TextureWrap) {
+        self.ctx.set_texture_wrap(self.id, glow::TEXTURE_WRAP_S, wrap);
+    }
+
+    /// Set the vertical (T) wrapping mode
+    pub fn set_wrap_v(&self, wrap: TextureWrap) {
+        self.ctx.set_texture_wrap(self.id, glow::TEXTURE_WRAP_T, wrap);
+    }
+
+    /// Generate the mipmap chain for this texture
+    ///
+    /// Required before a minification filter that samples mipmaps will have any effect.
+    pub fn generate_mipmaps(&self) {
+        self.ctx.generate_texture_mipmap(self.id);
+    }
+}
+
+impl Drop for Texture {
+    fn drop(&mut self) {
+        self.ctx.delete_texture(self.id);
+    }
+}
+
+/// An offscreen render target, backed by a framebuffer object
+///
+/// A single-sample `Surface` wraps a [`Texture`] as its color attachment and an
+/// optional depth-stencil renderbuffer, so that draw calls can be directed at a
+/// texture instead of the window; a multisampled one (see
+/// [`new_multisampled`](Surface::new_multisampled)) renders into renderbuffers instead. Bind it with [`Context::set_target`] and return to the
+/// window with [`Context::reset_target`]. This is the building block for
+/// post-processing, shadow maps, and ping-pong rendering.
+pub struct Surface {
+    pub(crate) ctx: Context,
+    pub(crate) id: u32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// The color attachment for a single-sample surface
+    pub(crate) texture: Option<Texture>,
+    /// The multisampled color attachment, when `samples > 1`
+    pub(crate) color_renderbuffer: Option<u32>,
+    pub(crate) depth_stencil: Option<u32>,
+    pub(crate) samples: u32,
+}
+
+impl Surface {
+    /// Create a new surface that renders into the given texture
+    ///
+    /// A depth-stencil renderbuffer sized to the texture is created and attached
+    /// as well, so the surface can be used for depth-tested 3D passes. An error
+    /// is returned if the resulting framebuffer is not complete. For a color-only
+    /// pass that has no use for depth or stencil, use [`new_color`](Surface::new_color).
+    pub fn new(ctx: &Context, texture: Texture) -> Result<Surface, GolemError> {
+        ctx.new_surface(texture, true)
+    }
+
+    /// Create a color-only surface that renders into the given texture
+    ///
+    /// Unlike [`new`](Surface::new), no depth-stencil renderbuffer is attached, so
+    /// post-processing and ping-pong passes avoid paying for depth they do not use.
+    /// An error is returned if the resulting framebuffer is not complete.
+    pub fn new_color(ctx: &Context, texture: Texture) -> Result<Surface, GolemError> {
+        ctx.new_surface(texture, false)
+    }
+
+    /// Create a multisampled surface for anti-aliased offscreen rendering
+    ///
+    /// The color and depth attachments are multisampled renderbuffers; because a
+    /// multisampled buffer cannot be sampled directly, use [`resolve_to`] to blit
+    /// the result into an ordinary [`Texture`] before drawing with it. The requested
+    /// sample count is clamped to `GL_MAX_SAMPLES`, and an error is returned if
+    /// multisampling is unsupported.
+    ///
+    /// [`resolve_to`]: Surface::resolve_to
+    pub fn new_multisampled(ctx: &Context, width: u32, height: u32, samples: u32) -> Result<Surface, GolemError> {
+        ctx.new_multisampled_surface(width, height, samples)
+    }
+
+    /// The [`Texture`] this surface renders into, if it is single-sampled
+    ///
+    /// Multisampled surfaces have no directly-readable texture; [`resolve_to`] them instead.
+    ///
+    /// [`resolve_to`]: Surface::resolve_to
+    pub fn texture(&self) -> Option<&Texture> {
+        self.texture.as_ref()
+    }
+
+    /// Resolve this (typically multisampled) surface into a single-sample texture
+    ///
+    /// Performs a `glBlitFramebuffer` of the color buffer into a framebuffer backed by
+    /// `texture`, downsampling with `GL_LINEAR`. The texture should match the surface size.
+    pub fn resolve_to(&self, texture: &Texture) -> Result<(), GolemError> {
+        self.ctx.resolve_surface(self.id, texture, self.width, self.height)
+    }
+
+    /// The raw OpenGL framebuffer handle backing this surface
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// The number of samples per pixel (1 for a non-multisampled surface)
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// The width in pixels of the surface
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The height in pixels of the surface
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+}
+
+impl Drop for Surface {
+    fn drop(&mut self) {
+        let renderbuffers: Vec<u32> = self.color_renderbuffer.iter().chain(self.depth_stencil.iter()).copied().collect();
+        self.ctx.delete_surface(self.id, &renderbuffers);
+    }
+}
+
+/// A factor multiplied into the source or destination color when blending
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SourceColor,
+    OneMinusSourceColor,
+    DestinationColor,
+    OneMinusDestinationColor,
+    SourceAlpha,
+    OneMinusSourceAlpha,
+    DestinationAlpha,
+    OneMinusDestinationAlpha,
+}
+
+impl BlendFactor {
+    pub(crate) fn gl_factor(self) -> u32 {
+        use BlendFactor::*;
+        match self {
+            Zero => glow::ZERO,
+            One => glow::ONE,
+            SourceColor => glow::SRC_COLOR,
+            OneMinusSourceColor => glow::ONE_MINUS_SRC_COLOR,
+            DestinationColor => glow::DST_COLOR,
+            OneMinusDestinationColor => glow::ONE_MINUS_DST_COLOR,
+            SourceAlpha => glow::SRC_ALPHA,
+            OneMinusSourceAlpha => glow::ONE_MINUS_SRC_ALPHA,
+            DestinationAlpha => glow::DST_ALPHA,
+            OneMinusDestinationAlpha => glow::ONE_MINUS_DST_ALPHA,
+        }
+    }
+}
+
+/// How incoming fragments are combined with the existing framebuffer contents
+///
+/// The color and alpha channels take independent factors, mapping onto
+/// `glBlendFuncSeparate`. The [`Default`] is standard "over" alpha blending.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct BlendMode {
+    pub source_color: BlendFactor,
+    pub destination_color: BlendFactor,
+    pub source_alpha: BlendFactor,
+    pub destination_alpha: BlendFactor,
+}
+
+impl Default for BlendMode {
+    fn default() -> BlendMode {
+        BlendMode {
+            source_color: BlendFactor::SourceAlpha,
+            destination_color: BlendFactor::OneMinusSourceAlpha,
+            source_alpha: BlendFactor::One,
+            destination_alpha: BlendFactor::OneMinusSourceAlpha,
+        }
+    }
+}
+
+/// The comparison used to decide whether a fragment passes the depth test
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum DepthTestFunction {
+    Never,
+    Less,
+    Equal,
+    LessOrEqual,
+    Greater,
+    NotEqual,
+    GreaterOrEqual,
+    Always,
+}
+
+impl DepthTestFunction {
+    pub(crate) fn gl_function(self) -> u32 {
+        use DepthTestFunction::*;
+        match self {
+            Never => glow::NEVER,
+            Less => glow::LESS,
+            Equal => glow::EQUAL,
+            LessOrEqual => glow::LEQUAL,
+            Greater => glow::GREATER,
+            NotEqual => glow::NOTEQUAL,
+            GreaterOrEqual => glow::GEQUAL,
+            Always => glow::ALWAYS,
+        }
+    }
+}
+
+/// Which triangle facings are discarded by face culling
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum Face {
+    Front,
+    Back,
+    FrontAndBack,
+}
+
+impl Face {
+    pub(crate) fn gl_face(self) -> u32 {
+        match self {
+            Face::Front => glow::FRONT,
+            Face::Back => glow::BACK,
+            Face::FrontAndBack => glow::FRONT_AND_BACK,
+        }
+    }
+}
+
+/// A value bound to a shader uniform via [`ShaderProgram`](crate::program::ShaderProgram)
+#[derive(Clone, Copy, Debug)]
+pub enum UniformValue {
+    Int(i32),
+    IVector2([i32; 2]),
+    IVector3([i32; 3]),
+    IVector4([i32; 4]),
+    Float(f32),
+    Vector2([f32; 2]),
+    Vector3([f32; 3]),
+    Vector4([f32; 4]),
+    Matrix2([f32; 4]),
+    Matrix3([f32; 9]),
+    Matrix4([f32; 16]),
+}
@@ -0,0 +1,193 @@
+use crate::buffer::VertexBuffer;
+use crate::objects::UniformValue;
+use crate::{Context, GolemError};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Where an [`Attribute`] sits in the shader pipeline
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum Position {
+    /// An input to the stage (`in` / `attribute`)
+    Input,
+    /// An output from the stage (`out` / `varying`)
+    Output,
+}
+
+/// A typed, named input or output of a shader stage
+#[derive(Clone)]
+pub enum Attribute {
+    /// A single `float`
+    Scalar(&'static str),
+    /// A `vecN`, where N is 2, 3, or 4
+    Vector(u32, &'static str),
+    /// A `matCxR` with the given column and row counts
+    Matrix(u32, u32, &'static str),
+}
+
+impl Attribute {
+    /// The GLSL identifier this attribute is declared with
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Scalar(name) => name,
+            Attribute::Vector(_, name) => name,
+            Attribute::Matrix(_, _, name) => name,
+        }
+    }
+
+    /// The number of `float` components this attribute occupies in a buffer
+    pub fn size(&self) -> i32 {
+        match self {
+            Attribute::Scalar(_) => 1,
+            Attribute::Vector(n, _) => *n as i32,
+            Attribute::Matrix(cols, rows, _) => (cols * rows) as i32,
+        }
+    }
+
+    fn glsl_type(&self) -> String {
+        match self {
+            Attribute::Scalar(_) => "float".to_owned(),
+            Attribute::Vector(n, _) => format!("vec{}", n),
+            Attribute::Matrix(cols, rows, _) if cols == rows => format!("mat{}", cols),
+            Attribute::Matrix(cols, rows, _) => format!("mat{}x{}", cols, rows),
+        }
+    }
+
+    pub(crate) fn as_glsl(&self, pos: Position, shader: &mut String) {
+        #[cfg(not(target_arch = "wasm32"))]
+        let qualifier = match pos {
+            Position::Input => "in",
+            Position::Output => "out",
+        };
+        #[cfg(target_arch = "wasm32")]
+        let qualifier = match pos {
+            Position::Input => "attribute",
+            Position::Output => "varying",
+        };
+        shader.push_str(qualifier);
+        shader.push(' ');
+        shader.push_str(&self.glsl_type());
+        shader.push(' ');
+        shader.push_str(self.name());
+        shader.push_str(";\n");
+    }
+}
+
+/// The type of a shader [`Uniform`]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum UniformType {
+    Scalar,
+    Vector(u32),
+    Matrix(u32),
+    Sampler2D,
+}
+
+/// A named value passed to every invocation of a shader
+#[derive(Clone)]
+pub struct Uniform {
+    name: &'static str,
+    u_type: UniformType,
+}
+
+impl Uniform {
+    pub fn new(name: &'static str, u_type: UniformType) -> Uniform {
+        Uniform { name, u_type }
+    }
+
+    pub(crate) fn as_glsl(&self, shader: &mut String) {
+        let glsl_type = match self.u_type {
+            UniformType::Scalar => "float".to_owned(),
+            UniformType::Vector(n) => format!("vec{}", n),
+            UniformType::Matrix(n) => format!("mat{}", n),
+            UniformType::Sampler2D => "sampler2D".to_owned(),
+        };
+        shader.push_str("uniform ");
+        shader.push_str(&glsl_type);
+        shader.push(' ');
+        shader.push_str(self.name);
+        shader.push_str(";\n");
+    }
+}
+
+/// A complete description of a shader, used to create a [`ShaderProgram`]
+pub struct ShaderDescription<'a> {
+    pub vertex_input: &'a [Attribute],
+    pub fragment_input: &'a [Attribute],
+    pub uniforms: &'a [Uniform],
+    pub vertex_shader: &'a str,
+    pub fragment_shader: &'a str,
+}
+
+/// A lazily-cached handle to a uniform's location within a program
+///
+/// The `PhantomData<*const u8>` keeps the location `!Send` and `!Sync`, matching
+/// the GL handle it wraps, which is only valid on the thread that owns the context.
+#[derive(Clone)]
+pub struct UniformLocation {
+    location: <glow::Context as glow::HasContext>::UniformLocation,
+    _marker: PhantomData<*const u8>,
+}
+
+impl UniformLocation {
+    pub(crate) fn new(location: <glow::Context as glow::HasContext>::UniformLocation) -> UniformLocation {
+        UniformLocation {
+            location,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn raw(&self) -> <glow::Context as glow::HasContext>::UniformLocation {
+        self.location.clone()
+    }
+}
+
+/// A linked vertex + fragment shader pair, ready to draw with
+pub struct ShaderProgram {
+    pub(crate) ctx: Context,
+    pub(crate) id: u32,
+    pub(crate) vertex: u32,
+    pub(crate) fragment: u32,
+    pub(crate) input: Vec<Attribute>,
+    pub(crate) uniform_locations: RefCell<HashMap<String, Option<UniformLocation>>>,
+}
+
+impl ShaderProgram {
+    /// Bind this program and its vertex layout, readying it for a draw call
+    pub fn bind(&mut self, vb: &VertexBuffer) {
+        self.ctx.bind_program(self.id, &self.input, vb);
+    }
+
+    /// Check whether this program is the one currently bound to the context
+    pub fn is_bound(&self) -> bool {
+        self.ctx.is_program_bound(self.id)
+    }
+
+    /// Look up and memoize a uniform's location ahead of the first draw
+    ///
+    /// Names GL reports as absent are cached as `None`, so they are never re-queried.
+    pub fn prepare_uniform(&self, name: &str) {
+        self.location(name);
+    }
+
+    /// Set a uniform by name, reusing the cached location when available
+    pub fn set_uniform(&self, name: &str, uniform: UniformValue) -> Result<(), GolemError> {
+        let location = self.location(name);
+        self.ctx.bind_uniform(location.as_ref(), uniform);
+        Ok(())
+    }
+
+    fn location(&self, name: &str) -> Option<UniformLocation> {
+        let mut cache = self.uniform_locations.borrow_mut();
+        if !cache.contains_key(name) {
+            let location = self.ctx.get_uniform_location(self.id, name);
+            cache.insert(name.to_owned(), location);
+        }
+        cache[name].clone()
+    }
+}
+
+impl Drop for ShaderProgram {
+    fn drop(&mut self) {
+        self.ctx.delete_shader(self.id, self.fragment, self.vertex);
+    }
+}